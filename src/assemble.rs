@@ -0,0 +1,301 @@
+//! A small two-pass assembler for the textual mnemonics produced by
+//! `Instruction`'s `Display` implementation (see `instruction.rs`).
+//!
+//! The first pass walks the source, assigning each instruction the address
+//! it will load at (ROMs start at `0x200`) and recording any label
+//! definitions (`loop:`) against that address. The second pass parses each
+//! instruction line into an `Instruction`, resolving label references used
+//! by `JP`, `CALL`, and `LD I` against the table built in the first pass,
+//! then encodes it to an opcode with `Instruction::encode`.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::instruction::Instruction;
+use crate::types::{Addr, RegId, Val};
+
+const ROM_BASE: u16 = 0x200;
+
+const MNEMONICS: &[&str] = &[
+    "SYS", "CLS", "RET", "JP", "CALL", "SE", "SNE", "OR", "AND", "XOR", "SUB", "SUBN", "SHR",
+    "SHL", "RND", "DRW", "SKP", "SKNP", "ADD", "LD",
+];
+
+#[derive(Debug)]
+pub enum AssembleError {
+    UnknownMnemonic(String),
+    UnknownLabel(String),
+    BadOperand(String),
+    BadRegister(String),
+    WrongOperandCount { mnemonic: String, expected: usize, got: usize },
+}
+
+impl fmt::Display for AssembleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AssembleError::UnknownMnemonic(m) => write!(f, "unknown mnemonic `{}`", m),
+            AssembleError::UnknownLabel(l) => write!(f, "unknown label `{}`", l),
+            AssembleError::BadOperand(o) => write!(f, "bad operand `{}`", o),
+            AssembleError::BadRegister(r) => write!(f, "bad register `{}`", r),
+            AssembleError::WrongOperandCount { mnemonic, expected, got } => write!(
+                f,
+                "`{}` expects {} operand(s), got {}",
+                mnemonic, expected, got
+            ),
+        }
+    }
+}
+
+/// Assemble `source` into a sequence of opcodes, one per instruction line,
+/// starting at `0x200` as CHIP-8 ROMs are loaded.
+pub fn assemble(source: &str) -> Result<Vec<u16>, AssembleError> {
+    let labels = collect_labels(source);
+
+    let mut opcodes = Vec::new();
+    for line in strip_comments_and_labels(source) {
+        let instr = parse_line(&line, &labels)?;
+        opcodes.push(instr.encode());
+    }
+
+    Ok(opcodes)
+}
+
+/// First pass: find every `label:` definition and the address it points at.
+fn collect_labels(source: &str) -> HashMap<String, Addr> {
+    let mut labels = HashMap::new();
+    let mut addr = ROM_BASE;
+
+    for raw_line in source.lines() {
+        let line = strip_comment(raw_line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(label) = line.strip_suffix(':') {
+            labels.insert(label.trim().to_string(), Addr(addr));
+            continue;
+        }
+
+        addr += 2;
+    }
+
+    labels
+}
+
+/// Strip comments and label definitions, leaving only instruction lines.
+fn strip_comments_and_labels(source: &str) -> Vec<String> {
+    source
+        .lines()
+        .map(|raw_line| strip_comment(raw_line).trim().to_string())
+        .filter(|line| !line.is_empty() && !line.ends_with(':'))
+        .collect()
+}
+
+fn strip_comment(line: &str) -> &str {
+    match line.find(';') {
+        Some(i) => &line[..i],
+        None => line,
+    }
+}
+
+fn parse_line(line: &str, labels: &HashMap<String, Addr>) -> Result<Instruction, AssembleError> {
+    let (mnemonic, rest) = match line.find(char::is_whitespace) {
+        Some(i) => (&line[..i], line[i..].trim()),
+        None => (line, ""),
+    };
+
+    let operands: Vec<&str> = if rest.is_empty() {
+        Vec::new()
+    } else {
+        rest.split(',').map(|s| s.trim()).collect()
+    };
+
+    let mnemonic = mnemonic.to_uppercase();
+
+    if !MNEMONICS.contains(&mnemonic.as_str()) {
+        return Err(AssembleError::UnknownMnemonic(mnemonic));
+    }
+
+    expect_count(&mnemonic, &operands, mnemonic_operand_count(&mnemonic))?;
+
+    use Instruction::*;
+    let instr = match mnemonic.as_str() {
+        "SYS" => Sys { addr: parse_addr(operands[0], labels)? },
+        "CLS" => Cls,
+        "RET" => Ret,
+        "JP" if operands.len() == 1 => Jump { addr: parse_addr(operands[0], labels)? },
+        "JP" => JpOfs { addr: parse_addr(operands[1], labels)? },
+        "CALL" => Call { addr: parse_addr(operands[0], labels)? },
+        "SE" if is_reg(operands[1]) => SeReg { x: parse_reg(operands[0])?, y: parse_reg(operands[1])? },
+        "SE" => SeVal { x: parse_reg(operands[0])?, k: parse_val(operands[1])? },
+        "SNE" if is_reg(operands[1]) => SneReg { x: parse_reg(operands[0])?, y: parse_reg(operands[1])? },
+        "SNE" => SneVal { x: parse_reg(operands[0])?, k: parse_val(operands[1])? },
+        "OR" => Or { x: parse_reg(operands[0])?, y: parse_reg(operands[1])? },
+        "AND" => And { x: parse_reg(operands[0])?, y: parse_reg(operands[1])? },
+        "XOR" => Xor { x: parse_reg(operands[0])?, y: parse_reg(operands[1])? },
+        "SUB" => Sub { x: parse_reg(operands[0])?, y: parse_reg(operands[1])? },
+        "SUBN" => SubN { x: parse_reg(operands[0])?, y: parse_reg(operands[1])? },
+        "SHR" => Shr { x: parse_reg(operands[0])?, y: parse_reg(operands[1])? },
+        "SHL" => Shl { x: parse_reg(operands[0])?, y: parse_reg(operands[1])? },
+        "RND" => Rnd { x: parse_reg(operands[0])?, k: parse_val(operands[1])? },
+        "DRW" => Drw {
+            x: parse_reg(operands[0])?,
+            y: parse_reg(operands[1])?,
+            n: parse_u8(operands[2])?,
+        },
+        "SKP" => Skp { x: parse_reg(operands[0])? },
+        "SKNP" => Sknp { x: parse_reg(operands[0])? },
+        "ADD" if operands[0].eq_ignore_ascii_case("i") => AddI { x: parse_reg(operands[1])? },
+        "ADD" if is_reg(operands[1]) => AddReg { x: parse_reg(operands[0])?, y: parse_reg(operands[1])? },
+        "ADD" => AddVal { x: parse_reg(operands[0])?, k: parse_val(operands[1])? },
+        "LD" => parse_ld(&operands, labels)?,
+        _ => return Err(AssembleError::UnknownMnemonic(mnemonic)),
+    };
+
+    Ok(instr)
+}
+
+fn parse_ld(operands: &[&str], labels: &HashMap<String, Addr>) -> Result<Instruction, AssembleError> {
+    use Instruction::*;
+
+    let (dst, src) = (operands[0], operands[1]);
+
+    let instr = if dst.eq_ignore_ascii_case("i") {
+        LdI { addr: parse_addr(src, labels)? }
+    } else if dst.eq_ignore_ascii_case("dt") {
+        LdDt { x: parse_reg(src)? }
+    } else if dst.eq_ignore_ascii_case("st") {
+        LdSt { x: parse_reg(src)? }
+    } else if dst.eq_ignore_ascii_case("f") {
+        LdDigit { x: parse_reg(src)? }
+    } else if dst.eq_ignore_ascii_case("b") {
+        Bcd { x: parse_reg(src)? }
+    } else if dst.eq_ignore_ascii_case("[i]") {
+        Store { x: parse_reg(src)? }
+    } else if src.eq_ignore_ascii_case("dt") {
+        Dt { x: parse_reg(dst)? }
+    } else if src.eq_ignore_ascii_case("k") {
+        LdKey { x: parse_reg(dst)? }
+    } else if src.eq_ignore_ascii_case("[i]") {
+        Read { x: parse_reg(dst)? }
+    } else if is_reg(src) {
+        LdReg { x: parse_reg(dst)?, y: parse_reg(src)? }
+    } else {
+        LdVal { x: parse_reg(dst)?, k: parse_val(src)? }
+    };
+
+    Ok(instr)
+}
+
+fn mnemonic_operand_count(mnemonic: &str) -> usize {
+    match mnemonic {
+        "CLS" | "RET" => 0,
+        "SYS" | "CALL" | "SKP" | "SKNP" => 1,
+        "JP" | "SE" | "SNE" | "LD" | "OR" | "AND" | "XOR" | "ADD" | "SUB" | "SUBN" | "SHR"
+        | "SHL" | "RND" => 2,
+        "DRW" => 3,
+        _ => 0,
+    }
+}
+
+fn expect_count(mnemonic: &str, operands: &[&str], expected: usize) -> Result<(), AssembleError> {
+    // JP takes 1 or 2 operands depending on whether it's an offset jump.
+    if mnemonic == "JP" {
+        if operands.len() == 1 || operands.len() == 2 {
+            return Ok(());
+        }
+    } else if operands.len() == expected {
+        return Ok(());
+    }
+
+    Err(AssembleError::WrongOperandCount {
+        mnemonic: mnemonic.to_string(),
+        expected,
+        got: operands.len(),
+    })
+}
+
+fn is_reg(operand: &str) -> bool {
+    parse_reg(operand).is_ok()
+}
+
+fn parse_reg(operand: &str) -> Result<RegId, AssembleError> {
+    if operand.len() == 2 && operand.as_bytes()[0].eq_ignore_ascii_case(&b'V') {
+        if let Ok(n) = u8::from_str_radix(&operand[1..], 16) {
+            return Ok(RegId(n));
+        }
+    }
+
+    Err(AssembleError::BadRegister(operand.to_string()))
+}
+
+fn parse_u8(operand: &str) -> Result<u8, AssembleError> {
+    parse_number(operand)
+        .and_then(|n| u8::try_from(n).map_err(|_| AssembleError::BadOperand(operand.to_string())))
+}
+
+fn parse_val(operand: &str) -> Result<Val, AssembleError> {
+    parse_u8(operand).map(Val)
+}
+
+fn parse_addr(operand: &str, labels: &HashMap<String, Addr>) -> Result<Addr, AssembleError> {
+    if let Some(addr) = labels.get(operand) {
+        return Ok(*addr);
+    }
+
+    parse_number(operand)
+        .map(Addr)
+        .map_err(|_| AssembleError::UnknownLabel(operand.to_string()))
+}
+
+fn parse_number(operand: &str) -> Result<u16, AssembleError> {
+    let parsed = if let Some(hex) = operand.strip_prefix("0x").or_else(|| operand.strip_prefix("0X")) {
+        u16::from_str_radix(hex, 16)
+    } else {
+        operand.parse::<u16>()
+    };
+
+    parsed.map_err(|_| AssembleError::BadOperand(operand.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every opcode `Instruction::interpret` accepts must survive a round
+    /// trip through its own textual form: `Display` renders it, then
+    /// `assemble` parses that text back into the same opcode. This is what
+    /// caught `SHR`/`SHL` silently dropping `y` when only `Instruction::
+    /// encode` (not `Display`) carried it through.
+    #[test]
+    fn assemble_round_trips_every_instruction_s_display_text() {
+        for opcode in 0..=0xFFFFu16 {
+            let instr = match Instruction::interpret(opcode) {
+                Some(instr) => instr,
+                None => continue,
+            };
+
+            let text = instr.to_string();
+            let reassembled = assemble(&text)
+                .unwrap_or_else(|e| panic!("failed to reassemble {:?} (`{}`): {:?}", instr, text, e));
+
+            assert_eq!(
+                reassembled,
+                vec![opcode],
+                "opcode {:#06x} rendered as `{}` but reassembled to {:#06x?}",
+                opcode,
+                text,
+                reassembled
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_mnemonic_is_reported_regardless_of_operand_count() {
+        assert!(matches!(
+            assemble("FOO V0, V1"),
+            Err(AssembleError::UnknownMnemonic(m)) if m == "FOO"
+        ));
+    }
+}
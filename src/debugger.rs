@@ -0,0 +1,216 @@
+//! An interactive stepping debugger wrapping a `Chip8`.
+//!
+//! Instead of `Chip8::start`'s unconditional loop, `Debugger` drives
+//! execution one `Chip8::step` cycle at a time so breakpoints, register and
+//! memory inspection, and a trace mode can interpose between cycles.
+
+use std::collections::HashSet;
+use std::io::{self, BufRead, Write};
+
+use byteorder::{BigEndian, ByteOrder};
+
+use crate::fault::Fault;
+use crate::instruction::Instruction;
+use crate::types::Addr;
+use crate::Chip8;
+
+pub struct Debugger {
+    chip8: Chip8,
+    breakpoints: HashSet<u16>,
+    trace: bool,
+}
+
+impl Debugger {
+    pub fn new(chip8: Chip8) -> Debugger {
+        Debugger {
+            chip8,
+            breakpoints: HashSet::new(),
+            trace: false,
+        }
+    }
+
+    pub fn set_breakpoint(&mut self, addr: u16) {
+        self.breakpoints.insert(addr);
+    }
+
+    pub fn clear_breakpoint(&mut self, addr: u16) {
+        self.breakpoints.remove(&addr);
+    }
+
+    pub fn set_trace(&mut self, trace: bool) {
+        self.trace = trace;
+    }
+
+    /// Step the VM `count` cycles, stopping early if a breakpoint is hit.
+    pub fn step(&mut self, count: usize) -> Result<(), Fault> {
+        for _ in 0..count {
+            if self.trace {
+                self.print_trace()?;
+            }
+
+            self.chip8.step()?;
+
+            if self.breakpoints.contains(&self.chip8.pc().0) {
+                println!("breakpoint hit at {}", self.chip8.pc());
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Run until a breakpoint is hit or the VM faults.
+    pub fn run(&mut self) -> Result<(), Fault> {
+        loop {
+            if self.trace {
+                self.print_trace()?;
+            }
+
+            self.chip8.step()?;
+
+            if self.breakpoints.contains(&self.chip8.pc().0) {
+                println!("breakpoint hit at {}", self.chip8.pc());
+                return Ok(());
+            }
+        }
+    }
+
+    /// Print the instruction about to execute, in trace mode.
+    fn print_trace(&self) -> Result<(), Fault> {
+        let pc = self.chip8.pc();
+        let slice = self.chip8.mem_range(pc.0 as usize, 2)?;
+        let opcode = BigEndian::read_u16(slice);
+        let instr = Instruction::interpret(opcode).ok_or(Fault::InvalidOpcode(opcode))?;
+        println!("{}: {}", pc, instr);
+        Ok(())
+    }
+
+    pub fn dump_registers(&self) {
+        for (i, v) in self.chip8.registers().iter().enumerate() {
+            print!("V{:X}={:<4}", i, v.0);
+            if i % 4 == 3 {
+                println!();
+            }
+        }
+
+        println!(
+            "I={}  PC={}  SP={}",
+            self.chip8.i(),
+            self.chip8.pc(),
+            self.chip8.sp()
+        );
+
+        for (i, addr) in self.chip8.stack_entries().iter().enumerate() {
+            println!("stack[{}]={}", i, addr);
+        }
+    }
+
+    pub fn dump_mem(&self, addr: u16, len: u16) {
+        let bytes = match self.chip8.mem_range(addr as usize, len as usize) {
+            Ok(bytes) => bytes,
+            Err(fault) => {
+                println!("fault: {:?}", fault);
+                return;
+            }
+        };
+
+        for (row, chunk) in bytes.chunks(16).enumerate() {
+            print!("{}: ", Addr(addr + (row * 16) as u16));
+            for byte in chunk {
+                print!("{:02x} ", byte);
+            }
+            println!();
+        }
+    }
+
+    /// Run an interactive command loop over stdin until the VM halts or the
+    /// user quits.
+    ///
+    /// Commands:
+    ///   break <addr>      set a breakpoint at `addr`
+    ///   clear <addr>      remove a breakpoint at `addr`
+    ///   step [n]          step `n` cycles (default 1)
+    ///   trace on|off      toggle trace mode
+    ///   regs              dump the V registers, I, PC, SP, and stack
+    ///   mem <addr> <len>  hexdump `len` bytes starting at `addr`
+    ///   continue          run until a breakpoint or fault
+    ///   quit              exit the debugger
+    pub fn repl(&mut self) {
+        let stdin = io::stdin();
+        self.prompt();
+
+        for line in stdin.lock().lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(_) => break,
+            };
+
+            if !self.exec_command(line.trim()) {
+                break;
+            }
+
+            self.prompt();
+        }
+    }
+
+    fn prompt(&self) {
+        print!("(chip8db) ");
+        io::stdout().flush().ok();
+    }
+
+    /// Execute a single debugger command. Returns `false` when the debugger
+    /// should stop, e.g. on `quit` or after the VM faults.
+    fn exec_command(&mut self, line: &str) -> bool {
+        let mut parts = line.split_whitespace();
+        let cmd = match parts.next() {
+            Some(cmd) => cmd,
+            None => return true,
+        };
+
+        match cmd {
+            "break" | "b" => match parts.next().and_then(parse_addr) {
+                Some(addr) => self.set_breakpoint(addr),
+                None => println!("usage: break <addr>"),
+            },
+            "clear" => match parts.next().and_then(parse_addr) {
+                Some(addr) => self.clear_breakpoint(addr),
+                None => println!("usage: clear <addr>"),
+            },
+            "step" | "s" => {
+                let count = parts.next().and_then(|s| s.parse().ok()).unwrap_or(1);
+                if let Err(fault) = self.step(count) {
+                    println!("fault: {:?}", fault);
+                    return false;
+                }
+            }
+            "trace" => match parts.next() {
+                Some("on") => self.set_trace(true),
+                Some("off") => self.set_trace(false),
+                _ => println!("usage: trace on|off"),
+            },
+            "regs" | "r" => self.dump_registers(),
+            "mem" | "m" => match (parts.next().and_then(parse_addr), parts.next().and_then(|s| s.parse().ok())) {
+                (Some(addr), Some(len)) => self.dump_mem(addr, len),
+                _ => println!("usage: mem <addr> <len>"),
+            },
+            "continue" | "c" => {
+                if let Err(fault) = self.run() {
+                    println!("fault: {:?}", fault);
+                    return false;
+                }
+            }
+            "quit" | "q" => return false,
+            _ => println!("unknown command `{}`", cmd),
+        }
+
+        true
+    }
+}
+
+fn parse_addr(s: &str) -> Option<u16> {
+    if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        u16::from_str_radix(hex, 16).ok()
+    } else {
+        s.parse().ok()
+    }
+}
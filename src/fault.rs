@@ -0,0 +1,19 @@
+//! Errors the interpreter can raise instead of panicking or corrupting
+//! memory. `Chip8::exec` and the `Stack` surface these so that a host
+//! embedding the emulator can recover instead of being taken down with it.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// `CALL` was executed with the stack already at its 16-entry capacity.
+    StackOverflow,
+    /// `RET` was executed with an empty stack.
+    StackUnderflow,
+    /// The fetched opcode doesn't match any known instruction encoding.
+    InvalidOpcode(u16),
+    /// An instruction tried to read or write outside of `mem`.
+    MemoryOutOfBounds { addr: usize, len: usize },
+    /// The opcode decoded to a valid `Instruction`, but `exec` has no
+    /// implementation for it (e.g. `SYS`, which calls native machine code
+    /// this interpreter can't run).
+    UnknownInstruction,
+}
@@ -1,3 +1,5 @@
+use core::fmt;
+
 use crate::types::{Addr, RegId, Val};
 
 #[derive(Debug)]
@@ -18,9 +20,9 @@ pub enum Instruction {
     Xor { x: RegId, y: RegId },
     AddReg { x: RegId, y: RegId },
     Sub { x: RegId, y: RegId },
-    Shr { x: RegId },
+    Shr { x: RegId, y: RegId },
     SubN { x: RegId, y: RegId },
-    Shl { x: RegId },
+    Shl { x: RegId, y: RegId },
     SneReg { x: RegId, y: RegId },
     LdI { addr: Addr },
     JpOfs { addr: Addr },
@@ -72,9 +74,9 @@ impl Instruction {
                 0x3 => Xor { x, y },                           // 8xy3 -> XOR Vx, Vy
                 0x4 => AddReg { x, y },                        // 8xy4 -> ADD Vx, Vy
                 0x5 => Sub { x, y },                           // 8xy5 -> SUB Vx, Vy
-                0x6 => Shr { x },                           // 8xy6 -> SHR Vx, Vy
+                0x6 => Shr { x, y },                           // 8xy6 -> SHR Vx, Vy
                 0x7 => SubN { x, y },                          // 8xy7 -> SUBN Vx, Vy
-                0xE => Shl { x },                           // 8xyE -> SHL Vx, Vy
+                0xE => Shl { x, y },                           // 8xyE -> SHL Vx, Vy
                 _ => return None,
             }
             0x9 => match n[3] {
@@ -108,10 +110,107 @@ impl Instruction {
 
         Some(inst)
     }
+
+    /// Encode this instruction back into its opcode, inverting `interpret`.
+    pub fn encode(&self) -> u16 {
+        use Instruction::*;
+
+        fn op(n0: u16, n1: u16, n2: u16, n3: u16) -> u16 {
+            (n0 << 12) | (n1 << 8) | (n2 << 4) | n3
+        }
+
+        fn addr_op(n0: u16, addr: Addr) -> u16 {
+            (n0 << 12) | (addr.0 & 0xFFF)
+        }
+
+        match *self {
+            Sys { addr } => addr_op(0x0, addr),
+            Cls => 0x00E0,
+            Ret => 0x00EE,
+            Jump { addr } => addr_op(0x1, addr),
+            Call { addr } => addr_op(0x2, addr),
+            SeVal { x, k } => op(0x3, x.0 as u16, (k.0 >> 4) as u16, (k.0 & 0xF) as u16),
+            SneVal { x, k } => op(0x4, x.0 as u16, (k.0 >> 4) as u16, (k.0 & 0xF) as u16),
+            SeReg { x, y } => op(0x5, x.0 as u16, y.0 as u16, 0x0),
+            LdVal { x, k } => op(0x6, x.0 as u16, (k.0 >> 4) as u16, (k.0 & 0xF) as u16),
+            AddVal { x, k } => op(0x7, x.0 as u16, (k.0 >> 4) as u16, (k.0 & 0xF) as u16),
+            LdReg { x, y } => op(0x8, x.0 as u16, y.0 as u16, 0x0),
+            Or { x, y } => op(0x8, x.0 as u16, y.0 as u16, 0x1),
+            And { x, y } => op(0x8, x.0 as u16, y.0 as u16, 0x2),
+            Xor { x, y } => op(0x8, x.0 as u16, y.0 as u16, 0x3),
+            AddReg { x, y } => op(0x8, x.0 as u16, y.0 as u16, 0x4),
+            Sub { x, y } => op(0x8, x.0 as u16, y.0 as u16, 0x5),
+            Shr { x, y } => op(0x8, x.0 as u16, y.0 as u16, 0x6),
+            SubN { x, y } => op(0x8, x.0 as u16, y.0 as u16, 0x7),
+            Shl { x, y } => op(0x8, x.0 as u16, y.0 as u16, 0xE),
+            SneReg { x, y } => op(0x9, x.0 as u16, y.0 as u16, 0x0),
+            LdI { addr } => addr_op(0xA, addr),
+            JpOfs { addr } => addr_op(0xB, addr),
+            Rnd { x, k } => op(0xC, x.0 as u16, (k.0 >> 4) as u16, (k.0 & 0xF) as u16),
+            Drw { x, y, n } => op(0xD, x.0 as u16, y.0 as u16, n as u16),
+            Skp { x } => op(0xE, x.0 as u16, 0x9, 0xE),
+            Sknp { x } => op(0xE, x.0 as u16, 0xA, 0x1),
+            Dt { x } => op(0xF, x.0 as u16, 0x0, 0x7),
+            LdKey { x } => op(0xF, x.0 as u16, 0x0, 0xA),
+            LdDt { x } => op(0xF, x.0 as u16, 0x1, 0x5),
+            LdSt { x } => op(0xF, x.0 as u16, 0x1, 0x8),
+            AddI { x } => op(0xF, x.0 as u16, 0x1, 0xE),
+            LdDigit { x } => op(0xF, x.0 as u16, 0x2, 0x9),
+            Bcd { x } => op(0xF, x.0 as u16, 0x3, 0x3),
+            Store { x } => op(0xF, x.0 as u16, 0x5, 0x5),
+            Read { x } => op(0xF, x.0 as u16, 0x6, 0x5),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    /// Render the instruction as its canonical assembly mnemonic, e.g.
+    /// `SE Vx, kk` or `DRW Vx, Vy, n`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Instruction::*;
+
+        match *self {
+            Sys { addr } => write!(f, "SYS {}", addr),
+            Cls => write!(f, "CLS"),
+            Ret => write!(f, "RET"),
+            Jump { addr } => write!(f, "JP {}", addr),
+            Call { addr } => write!(f, "CALL {}", addr),
+            SeVal { x, k } => write!(f, "SE {}, {}", x, k),
+            SneVal { x, k } => write!(f, "SNE {}, {}", x, k),
+            SeReg { x, y } => write!(f, "SE {}, {}", x, y),
+            LdVal { x, k } => write!(f, "LD {}, {}", x, k),
+            AddVal { x, k } => write!(f, "ADD {}, {}", x, k),
+            LdReg { x, y } => write!(f, "LD {}, {}", x, y),
+            Or { x, y } => write!(f, "OR {}, {}", x, y),
+            And { x, y } => write!(f, "AND {}, {}", x, y),
+            Xor { x, y } => write!(f, "XOR {}, {}", x, y),
+            AddReg { x, y } => write!(f, "ADD {}, {}", x, y),
+            Sub { x, y } => write!(f, "SUB {}, {}", x, y),
+            Shr { x, y } => write!(f, "SHR {}, {}", x, y),
+            SubN { x, y } => write!(f, "SUBN {}, {}", x, y),
+            Shl { x, y } => write!(f, "SHL {}, {}", x, y),
+            SneReg { x, y } => write!(f, "SNE {}, {}", x, y),
+            LdI { addr } => write!(f, "LD I, {}", addr),
+            JpOfs { addr } => write!(f, "JP V0, {}", addr),
+            Rnd { x, k } => write!(f, "RND {}, {}", x, k),
+            Drw { x, y, n } => write!(f, "DRW {}, {}, {}", x, y, n),
+            Skp { x } => write!(f, "SKP {}", x),
+            Sknp { x } => write!(f, "SKNP {}", x),
+            Dt { x } => write!(f, "LD {}, DT", x),
+            LdKey { x } => write!(f, "LD {}, K", x),
+            LdDt { x } => write!(f, "LD DT, {}", x),
+            LdSt { x } => write!(f, "LD ST, {}", x),
+            AddI { x } => write!(f, "ADD I, {}", x),
+            LdDigit { x } => write!(f, "LD F, {}", x),
+            Bcd { x } => write!(f, "LD B, {}", x),
+            Store { x } => write!(f, "LD [I], {}", x),
+            Read { x } => write!(f, "LD {}, [I]", x),
+        }
+    }
 }
 
 fn bytes(x: u16) -> [u8; 2] {
-    let lo = (x & std::u8::MAX as u16) as u8;
+    let lo = (x & u8::MAX as u16) as u8;
     let hi = (x >> 8) as u8;
 
     [hi, lo]
@@ -127,3 +226,26 @@ fn nibbles(x: u16) -> [u8; 4] {
     return result;
 }
 
+#[cfg(test)]
+mod tests {
+    use super::Instruction;
+
+    /// Every opcode `interpret` accepts must `encode` back to itself --
+    /// covers the full `8xy6`/`8xyE` (`SHR`/`SHL`) nibble range, which once
+    /// dropped `y` on the way through and silently zeroed it back out.
+    #[test]
+    fn encode_round_trips_every_interpretable_opcode() {
+        for opcode in 0..=0xFFFFu16 {
+            if let Some(instr) = Instruction::interpret(opcode) {
+                assert_eq!(
+                    instr.encode(),
+                    opcode,
+                    "opcode {:#06x} decoded to {:?} but re-encoded as {:#06x}",
+                    opcode,
+                    instr,
+                    instr.encode()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! The 16-key hex keypad input state.
+//!
+//! `Chip8` holds its key state behind the `Keypad` trait so a front-end
+//! (SDL2, a web canvas, ...) only has to push key-down/key-up events in;
+//! the core stays decoupled from how or where those events originate.
+
+pub trait Keypad {
+    fn key_down(&mut self, key: u8);
+    fn key_up(&mut self, key: u8);
+    fn is_down(&self, key: u8) -> bool;
+
+    /// The lowest-numbered key currently pressed, if any. `LD Vx, K` blocks
+    /// until some key is latched, so it polls this every cycle.
+    fn first_pressed(&self) -> Option<u8> {
+        (0..16).find(|&key| self.is_down(key))
+    }
+}
+
+/// The default `Keypad`: sixteen independently tracked hex keys.
+pub struct HexKeypad {
+    keys: [bool; 16],
+}
+
+impl HexKeypad {
+    pub fn new() -> HexKeypad {
+        HexKeypad { keys: [false; 16] }
+    }
+}
+
+impl Default for HexKeypad {
+    fn default() -> HexKeypad {
+        HexKeypad::new()
+    }
+}
+
+impl Keypad for HexKeypad {
+    fn key_down(&mut self, key: u8) {
+        self.keys[key as usize & 0xF] = true;
+    }
+
+    fn key_up(&mut self, key: u8) {
+        self.keys[key as usize & 0xF] = false;
+    }
+
+    fn is_down(&self, key: u8) -> bool {
+        self.keys[key as usize & 0xF]
+    }
+}
@@ -0,0 +1,831 @@
+// Copyright © 2019 Cormac O'Brien.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! The CHIP-8 core: memory, registers, stack, display, and the
+//! decode/execute loop. Builds as `no_std` when the `std` feature is
+//! disabled, so it can target wasm or embedded platforms; the `std` feature
+//! (on by default) adds the filesystem ROM loader, the assembler, and the
+//! stepping debugger, none of which the core itself needs.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+extern crate byteorder;
+
+#[cfg(feature = "std")]
+pub mod assemble;
+#[cfg(feature = "std")]
+pub mod debugger;
+pub mod fault;
+pub mod instruction;
+pub mod keypad;
+pub mod rng;
+pub mod timers;
+pub mod types;
+
+use alloc::boxed::Box;
+use core::ops::Deref;
+use core::time::Duration;
+
+#[cfg(feature = "std")]
+use std::fs::File;
+#[cfg(feature = "std")]
+use std::io::Read as _;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use byteorder::{BigEndian, ByteOrder};
+
+use fault::Fault;
+use instruction::Instruction;
+use keypad::{HexKeypad, Keypad};
+use rng::{Rng, XorShiftRng};
+use timers::Timers;
+use types::{Addr, RegId, Val};
+
+/// Default instructions executed per 60 Hz frame, roughly matching the
+/// COSMAC VIP's original CHIP-8 execution speed.
+const DEFAULT_CYCLES_PER_FRAME: u32 = 10;
+
+/// Where the built-in hex digit font is installed in low memory.
+const FONT_BASE: u16 = 0x050;
+
+const DIGITS: [[u8; 5]; 16] = [
+    [0xF0, 0x90, 0x90, 0x90, 0xF0],
+    [0x20, 0x60, 0x20, 0x20, 0x70],
+    [0xF0, 0x10, 0xF0, 0x80, 0xF0],
+    [0xF0, 0x10, 0xF0, 0x10, 0xF0],
+    [0x90, 0x90, 0xF0, 0x10, 0x10],
+    [0xF0, 0x80, 0xF0, 0x10, 0xF0],
+    [0xF0, 0x80, 0xF0, 0x90, 0xF0],
+    [0xF0, 0x10, 0x20, 0x40, 0x40],
+    [0xF0, 0x90, 0xF0, 0x90, 0xF0],
+    [0xF0, 0x90, 0xF0, 0x10, 0xF0],
+    [0xF0, 0x90, 0xF0, 0x90, 0x90],
+    [0xE0, 0x90, 0xE0, 0x90, 0xE0],
+    [0xF0, 0x80, 0x80, 0x80, 0xF0],
+    [0xE0, 0x90, 0x90, 0x90, 0xE0],
+    [0xF0, 0x80, 0xF0, 0x80, 0xF0],
+    [0xF0, 0x80, 0xF0, 0x80, 0x80],
+];
+
+/// A seed derived from the current time, for `Chip8::new`'s default RNG.
+/// Not used when a caller wants determinism -- use `Chip8::with_seed` or
+/// `Chip8::with_rng` instead.
+#[cfg(feature = "std")]
+fn default_seed() -> u32 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0x2545_F491)
+}
+
+/// Without `std` there's no wall clock to seed from, so fall back to a
+/// fixed constant. Use `Chip8::with_seed` for anything that needs entropy.
+#[cfg(not(feature = "std"))]
+fn default_seed() -> u32 {
+    0x2545_F491
+}
+
+struct Display {
+    pixels: [[u8; 64]; 32],
+}
+
+impl Display {
+    pub fn new() -> Display {
+        Display {
+            pixels: [[0; 64]; 32],
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.pixels = [[0; 64]; 32];
+    }
+
+    pub fn draw(&mut self, x: Val, y: Val, sprite: &[u8]) -> bool {
+        let mut collision = false;
+
+        for (y_ofs, byte) in sprite.iter().enumerate() {
+            // don't draw off the screen
+            if y.0 as usize + y_ofs >= self.pixels.len() {
+                break;
+            }
+
+            for (x_ofs, shift) in (0..8).rev().enumerate() {
+                // don't draw off the screen
+                if x.0 as usize + x_ofs >= self.pixels[0].len() {
+                    break;
+                }
+
+                let bit = (byte >> shift) & 1u8;
+
+                self.pixels[y.0 as usize + y_ofs][x.0 as usize + x_ofs] ^= bit;
+                collision = collision || self.pixels[y.0 as usize][x.0 as usize] != bit;
+            }
+        }
+
+        collision
+    }
+
+    #[cfg(feature = "std")]
+    pub fn print(&self) {
+        for row in self.pixels.iter() {
+            for pix in row.iter() {
+                if *pix == 1 {
+                    print!("#");
+                } else {
+                    print!(" ");
+                }
+            }
+
+            println!();
+        }
+    }
+}
+
+struct Pc(Addr);
+
+impl Pc {
+    pub fn new() -> Pc {
+        Pc(Addr(0x200))
+    }
+
+    pub fn get(&self) -> Addr {
+        self.0
+    }
+
+    pub fn increment(&mut self) {
+        (self.0).0 = (self.0).0 + 2;
+    }
+
+    /// Increment PC if cond is true.
+    pub fn increment_cond(&mut self, cond: bool) {
+        (self.0).0 = (self.0).0 + 2 * (cond as u16);
+    }
+
+    pub fn jump(&mut self, addr: Addr) {
+        (self.0).0 = addr.0 - 2; // pc will advance to correct address next cycle
+    }
+}
+
+struct Reg(Val);
+
+impl Reg {
+    pub fn new() -> Reg {
+        Reg(Val(0))
+    }
+
+    pub fn get(&self) -> Val {
+        self.0
+    }
+
+    pub fn set(&mut self, k: Val) {
+        self.0 = k
+    }
+
+    pub fn add(&mut self, k: Val) -> bool {
+        let (val, carry) = self.0.overflowing_add(*k);
+        self.0 = Val(val);
+        return carry;
+    }
+
+    pub fn sub(&mut self, k: Val) -> bool {
+        let (val, carry) = self.0.overflowing_sub(*k);
+        self.0 = Val(val);
+        return carry;
+    }
+
+    pub fn shr(&mut self) {
+        use core::ops::Shr;
+        self.0 = Val(self.0.shr(1));
+    }
+
+    pub fn shl(&mut self) {
+        use core::ops::Shl;
+        self.0 = Val(self.0.shl(1));
+    }
+}
+
+impl Deref for Reg {
+    type Target = Val;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+struct Stack {
+    stack: [Addr; 16],
+    sp: usize,
+}
+
+impl Stack {
+    pub fn new() -> Stack {
+        Stack {
+            stack: [Addr(0); 16],
+            sp: 0,
+        }
+    }
+
+    pub fn push(&mut self, addr: Addr) -> Result<(), Fault> {
+        if self.sp >= self.stack.len() {
+            return Err(Fault::StackOverflow);
+        }
+
+        self.stack[self.sp] = addr;
+        self.sp += 1;
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Result<Addr, Fault> {
+        if self.sp == 0 {
+            return Err(Fault::StackUnderflow);
+        }
+
+        self.sp -= 1;
+        Ok(self.stack[self.sp])
+    }
+}
+
+pub struct Chip8 {
+    mem: [u8; 4096],
+    register_v: [Reg; 16],
+    register_i: Addr,
+    timers: Timers,
+    pc: Pc,
+    sp: u8,
+    stack: Stack,
+    display: Display,
+    rng: Box<dyn Rng>,
+    keypad: Box<dyn Keypad>,
+    cycles_per_frame: u32,
+}
+
+impl Default for Chip8 {
+    fn default() -> Chip8 {
+        Chip8::new()
+    }
+}
+
+impl Chip8 {
+    pub fn new() -> Chip8 {
+        Chip8::with_rng(Box::new(XorShiftRng::new(default_seed())))
+    }
+
+    /// Construct a `Chip8` whose `RND` opcode is driven by `XorShiftRng`
+    /// seeded with `seed`, for deterministic replay.
+    pub fn with_seed(seed: u32) -> Chip8 {
+        Chip8::with_rng(Box::new(XorShiftRng::new(seed)))
+    }
+
+    /// Construct a `Chip8` with an arbitrary `RND` source, e.g. a
+    /// `FixedRng` in tests.
+    pub fn with_rng(rng: Box<dyn Rng>) -> Chip8 {
+        let mut mem = [0; 4096];
+        let font_base = FONT_BASE as usize;
+        for (i, glyph) in DIGITS.iter().enumerate() {
+            mem[font_base + i * 5..font_base + i * 5 + 5].copy_from_slice(glyph);
+        }
+
+        Chip8 {
+            mem,
+            register_v: [
+                Reg::new(),
+                Reg::new(),
+                Reg::new(),
+                Reg::new(),
+                Reg::new(),
+                Reg::new(),
+                Reg::new(),
+                Reg::new(),
+                Reg::new(),
+                Reg::new(),
+                Reg::new(),
+                Reg::new(),
+                Reg::new(),
+                Reg::new(),
+                Reg::new(),
+                Reg::new(),
+            ],
+            register_i: Addr(0),
+            timers: Timers::new(),
+            pc: Pc::new(),
+            sp: 0,
+            stack: Stack::new(),
+            display: Display::new(),
+            rng,
+            keypad: Box::new(HexKeypad::new()),
+            cycles_per_frame: DEFAULT_CYCLES_PER_FRAME,
+        }
+    }
+
+    /// Set how many instructions `start` executes per 60 Hz frame. Higher
+    /// values run the CPU faster relative to the timers.
+    pub fn set_cycles_per_frame(&mut self, cycles: u32) {
+        self.cycles_per_frame = cycles;
+    }
+
+    /// Replace the key input source, e.g. with a front-end-backed `Keypad`.
+    pub fn set_keypad(&mut self, keypad: Box<dyn Keypad>) {
+        self.keypad = keypad;
+    }
+
+    /// Latch a key press for `key` (0x0-0xF).
+    pub fn key_down(&mut self, key: u8) {
+        self.keypad.key_down(key);
+    }
+
+    /// Release a previously latched key press for `key` (0x0-0xF).
+    pub fn key_up(&mut self, key: u8) {
+        self.keypad.key_up(key);
+    }
+
+    /// Advance the delay/sound timers by `elapsed` wall-clock time.
+    pub fn tick(&mut self, elapsed: Duration) {
+        self.timers.tick(elapsed);
+    }
+
+    /// Whether the sound timer is active, i.e. a front-end should beep.
+    pub fn sound_active(&self) -> bool {
+        self.timers.sound_active()
+    }
+
+    pub(crate) fn reg(&self, id: RegId) -> &Reg {
+        &self.register_v[id.0 as usize]
+    }
+
+    pub(crate) fn reg_mut(&mut self, id: RegId) -> &mut Reg {
+        &mut self.register_v[id.0 as usize]
+    }
+
+    pub fn set_carry(&mut self, carry: bool) {
+        self.register_v[15].set(Val(carry as u8));
+    }
+
+    /// Check that a `len`-byte range starting at `addr` falls within `mem`.
+    fn check_mem(&self, addr: usize, len: usize) -> Result<(), Fault> {
+        if addr + len > self.mem.len() {
+            Err(Fault::MemoryOutOfBounds { addr, len })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Copy `rom` into memory starting at `0x200`, where CHIP-8 ROMs load.
+    pub fn load_bytes(&mut self, rom: &[u8]) -> Result<(), Fault> {
+        const ROM_BASE: usize = 0x200;
+        const MAX_LEN: usize = 4096 - ROM_BASE;
+
+        if rom.len() > MAX_LEN {
+            return Err(Fault::MemoryOutOfBounds {
+                addr: ROM_BASE,
+                len: rom.len(),
+            });
+        }
+
+        self.mem[ROM_BASE..ROM_BASE + rom.len()].copy_from_slice(rom);
+        Ok(())
+    }
+
+    /// Load a ROM from the filesystem. Requires the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn load<P>(&mut self, path: P)
+    where
+        P: AsRef<Path>,
+    {
+        let mut f = File::open(path).unwrap();
+        let len = f.metadata().unwrap().len() as usize;
+        let mut rom = alloc::vec![0u8; len];
+        f.read_exact(&mut rom).unwrap();
+        self.load_bytes(&rom).unwrap();
+    }
+
+    /// Fetch, decode, and execute exactly one instruction.
+    pub fn step(&mut self) -> Result<(), Fault> {
+        let pc_val = self.pc.get().0 as usize;
+        self.check_mem(pc_val, 2)?;
+
+        let opcode = BigEndian::read_u16(&self.mem[pc_val..pc_val + 2]);
+        let instr = Instruction::interpret(opcode).ok_or(Fault::InvalidOpcode(opcode))?;
+
+        self.exec(instr)
+    }
+
+    /// Run until a fault occurs, driving instructions at `cycles_per_frame`
+    /// per 60 Hz frame. Requires the `std` feature for wall-clock timing.
+    #[cfg(feature = "std")]
+    pub fn start(&mut self) -> Result<(), Fault> {
+        let frame = Duration::from_nanos(1_000_000_000 / 60);
+        let mut last_frame = std::time::Instant::now();
+
+        loop {
+            for _ in 0..self.cycles_per_frame {
+                let pc = self.pc.get();
+
+                if let Err(fault) = self.step() {
+                    eprintln!("fault at pc={}: {:?}", pc, fault);
+                    return Err(fault);
+                }
+            }
+
+            // wait out the rest of the 60 Hz frame
+            let busy = last_frame.elapsed();
+            if busy < frame {
+                std::thread::sleep(frame - busy);
+            }
+
+            let now = std::time::Instant::now();
+            self.tick(now.duration_since(last_frame));
+            last_frame = now;
+
+            self.display.print();
+        }
+    }
+
+    /// The program counter of the next instruction to execute.
+    pub fn pc(&self) -> Addr {
+        self.pc.get()
+    }
+
+    /// The `I` register.
+    pub fn i(&self) -> Addr {
+        self.register_i
+    }
+
+    /// The stack pointer, i.e. the number of return addresses on the stack.
+    pub fn sp(&self) -> usize {
+        self.stack.sp
+    }
+
+    /// The return addresses currently on the stack, oldest first.
+    pub fn stack_entries(&self) -> &[Addr] {
+        &self.stack.stack[..self.stack.sp]
+    }
+
+    /// The current value of each `V` register.
+    pub fn registers(&self) -> [Val; 16] {
+        let mut vals = [Val(0); 16];
+        for (i, val) in vals.iter_mut().enumerate() {
+            *val = self.reg(RegId(i as u8)).get();
+        }
+        vals
+    }
+
+    /// Read a `len`-byte range of memory starting at `addr`.
+    pub fn mem_range(&self, addr: usize, len: usize) -> Result<&[u8], Fault> {
+        self.check_mem(addr, len)?;
+        Ok(&self.mem[addr..addr + len])
+    }
+
+    pub fn exec(&mut self, instruction: Instruction) -> Result<(), Fault> {
+        use Instruction::*;
+        match instruction {
+            Sys { addr: _ } => return Err(Fault::UnknownInstruction),
+            Cls => self.display.clear(),
+            Ret => {
+                let addr = self.stack.pop()?;
+                self.pc.jump(addr);
+            }
+            Jump { addr } => self.pc.jump(addr),
+            Call { addr } => {
+                self.stack.push(self.pc.get())?;
+                self.pc.jump(addr);
+            }
+            SeVal { x, k } => self.pc.increment_cond(self.reg(x).get() == k),
+            SneVal { x, k } => self.pc.increment_cond(self.reg(x).get() != k),
+            SeReg { x, y } => self
+                .pc
+                .increment_cond(self.reg(x).get() == self.reg(y).get()),
+            LdVal { x, k } => self.reg_mut(x).set(k),
+            AddVal { x, k } => {
+                let _carry = self.reg_mut(x).add(k); // TODO: does this ignore overflow?
+            }
+            LdReg { x, y } => {
+                let y_val = self.reg(y).get();
+                self.reg_mut(x).set(y_val);
+            }
+            Or { x, y } => {
+                let (x_val, y_val) = (self.reg(x).get(), self.reg(y).get());
+                self.reg_mut(x).set(Val(*x_val | *y_val));
+            }
+            And { x, y } => {
+                let (x_val, y_val) = (self.reg(x).get(), self.reg(y).get());
+                self.reg_mut(x).set(Val(*x_val & *y_val));
+            }
+            Xor { x, y } => {
+                let (x_val, y_val) = (self.reg(x).get(), self.reg(y).get());
+                self.reg_mut(x).set(Val(*x_val & *y_val));
+            }
+            AddReg { x, y } => {
+                let y_val = self.reg(y).get();
+                let carry = self.reg_mut(x).add(y_val);
+                self.set_carry(carry);
+            }
+            Sub { x, y } => {
+                let y_val = self.reg(y).get();
+                let not_carry = !self.reg_mut(x).sub(y_val);
+                self.set_carry(not_carry); // SUB sets carry flag if it does not underflow
+            }
+            Shr { x, y: _ } => self.reg_mut(x).shr(),
+            SubN { x, y } => {
+                let x_val = self.reg(x).get();
+                let not_carry = !self.reg_mut(y).sub(x_val);
+                self.set_carry(not_carry);
+            }
+            Shl { x, y: _ } => self.reg_mut(x).shl(),
+            SneReg { x, y } => self
+                .pc
+                .increment_cond(self.reg(x).get() == self.reg(y).get()),
+            LdI { addr } => self.register_i = addr,
+            JpOfs { addr } => {
+                let v0_val = self.reg(RegId(0)).get().0 as u16;
+                let new_addr = Addr(addr.0 + v0_val);
+                self.register_i = new_addr;
+            }
+            Rnd { x, k } => {
+                let byte = self.rng.next_u8();
+                self.reg_mut(x).set(Val(byte & k.0));
+            }
+            Drw { x, y, n } => {
+                let addr = self.register_i.0 as usize;
+                let len = n as usize;
+                self.check_mem(addr, len)?;
+                let sprite = &self.mem[addr..addr + len];
+                let collision = self
+                    .display
+                    .draw(self.reg(x).get(), self.reg(y).get(), sprite);
+                self.set_carry(collision);
+            }
+            Skp { x } => {
+                let key = self.reg(x).get().0;
+                self.pc.increment_cond(self.keypad.is_down(key));
+            }
+            Sknp { x } => {
+                let key = self.reg(x).get().0;
+                self.pc.increment_cond(!self.keypad.is_down(key));
+            }
+            Dt { x } => {
+                let val = Val(self.timers.delay());
+                self.reg_mut(x).set(val);
+            }
+            LdKey { x } => match self.keypad.first_pressed() {
+                Some(key) => self.reg_mut(x).set(Val(key)),
+                // block: leave PC where it is so this instruction re-runs
+                // next cycle, matching the hardware's wait-for-key semantics
+                None => return Ok(()),
+            },
+            LdDt { x } => self.timers.set_delay(self.reg(x).get().0),
+            LdSt { x } => self.timers.set_sound(self.reg(x).get().0),
+            AddI { x } => self.register_i = Addr(self.register_i.0 + self.reg(x).get().0 as u16),
+            LdDigit { x } => {
+                let digit = self.reg(x).get().0 & 0xF;
+                self.register_i = Addr(FONT_BASE + digit as u16 * 5);
+            }
+            Bcd { x } => {
+                let addr = self.register_i.0 as usize;
+                self.check_mem(addr, 3)?;
+
+                let mut x_val = self.reg(x).get().0;
+                let ones = x_val % 10;
+                x_val /= 10;
+                let tens = x_val % 10;
+                x_val /= 10;
+                let hundreds = x_val % 10;
+                self.mem[addr] = hundreds;
+                self.mem[addr + 1] = tens;
+                self.mem[addr + 2] = ones;
+            }
+            Store { x } => {
+                let addr = self.register_i.0 as usize;
+                self.check_mem(addr, x.0 as usize)?;
+                for k in 0..x.0 {
+                    let id = RegId(k);
+                    self.mem[addr + k as usize] = self.reg(id).get().0;
+                }
+            }
+            Read { x } => {
+                let addr = self.register_i.0 as usize;
+                self.check_mem(addr, x.0 as usize)?;
+                for k in 0..x.0 {
+                    let id = RegId(k);
+                    let val = self.mem[addr + k as usize];
+                    self.reg_mut(id).set(Val(val));
+                }
+            }
+        }
+
+        self.pc.increment();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A ROM of back-to-back `RND V0, 0xFF` instructions, so every step
+    /// pulls one byte straight off the RNG into `V0`.
+    fn rnd_rom(count: usize) -> alloc::vec::Vec<u8> {
+        let mut rom = alloc::vec::Vec::new();
+        for _ in 0..count {
+            rom.extend_from_slice(&[0xC0, 0xFF]); // RND V0, 0xFF
+        }
+        rom
+    }
+
+    /// The whole premise of a pluggable, seedable RNG is that a ROM + seed
+    /// reproduces the exact same frames -- that's what makes regression
+    /// tests and record/replay possible. Verify two `Chip8`s seeded alike
+    /// actually produce identical `RND` results.
+    #[test]
+    fn with_seed_is_deterministic_across_identical_roms() {
+        let rom = rnd_rom(16);
+
+        let mut a = Chip8::with_seed(0xC0FFEE);
+        a.load_bytes(&rom).unwrap();
+
+        let mut b = Chip8::with_seed(0xC0FFEE);
+        b.load_bytes(&rom).unwrap();
+
+        for _ in 0..16 {
+            a.step().unwrap();
+            b.step().unwrap();
+            assert_eq!(a.registers()[0], b.registers()[0]);
+        }
+    }
+
+    #[test]
+    fn with_rng_drives_rnd_from_the_injected_source() {
+        let rom = rnd_rom(4);
+
+        let mut chip8 = Chip8::with_rng(Box::new(rng::FixedRng::new(alloc::vec![0x0F])));
+        chip8.load_bytes(&rom).unwrap();
+
+        for _ in 0..4 {
+            chip8.step().unwrap();
+            assert_eq!(chip8.registers()[0], Val(0x0F));
+        }
+    }
+
+    #[test]
+    fn stack_overflows_after_16_calls() {
+        let mut stack = Stack::new();
+        for _ in 0..16 {
+            stack.push(Addr(0x200)).unwrap();
+        }
+        assert_eq!(stack.push(Addr(0x200)), Err(Fault::StackOverflow));
+    }
+
+    #[test]
+    fn stack_underflows_when_popped_empty() {
+        let mut stack = Stack::new();
+        assert_eq!(stack.pop(), Err(Fault::StackUnderflow));
+    }
+
+    #[test]
+    fn load_bytes_rejects_a_rom_too_large_for_memory() {
+        let mut chip8 = Chip8::new();
+        let oversized = alloc::vec![0u8; 4096 - 0x200 + 1];
+        assert_eq!(
+            chip8.load_bytes(&oversized),
+            Err(Fault::MemoryOutOfBounds {
+                addr: 0x200,
+                len: oversized.len(),
+            })
+        );
+    }
+
+    #[test]
+    fn drw_faults_when_the_sprite_runs_past_the_end_of_memory() {
+        let mut chip8 = Chip8::new();
+        chip8.register_i = Addr(4096 - 1);
+        let result = chip8.exec(Instruction::Drw {
+            x: RegId(0),
+            y: RegId(0),
+            n: 2,
+        });
+        assert_eq!(
+            result,
+            Err(Fault::MemoryOutOfBounds {
+                addr: 4096 - 1,
+                len: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn bcd_faults_when_i_leaves_no_room_for_three_bytes() {
+        let mut chip8 = Chip8::new();
+        chip8.register_i = Addr(4096 - 1);
+        let result = chip8.exec(Instruction::Bcd { x: RegId(0) });
+        assert_eq!(
+            result,
+            Err(Fault::MemoryOutOfBounds {
+                addr: 4096 - 1,
+                len: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn store_faults_when_i_leaves_no_room_for_the_registers() {
+        let mut chip8 = Chip8::new();
+        chip8.register_i = Addr(4096 - 1);
+        let result = chip8.exec(Instruction::Store { x: RegId(2) });
+        assert_eq!(
+            result,
+            Err(Fault::MemoryOutOfBounds {
+                addr: 4096 - 1,
+                len: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn read_faults_when_i_leaves_no_room_for_the_registers() {
+        let mut chip8 = Chip8::new();
+        chip8.register_i = Addr(4096 - 1);
+        let result = chip8.exec(Instruction::Read { x: RegId(2) });
+        assert_eq!(
+            result,
+            Err(Fault::MemoryOutOfBounds {
+                addr: 4096 - 1,
+                len: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn skp_skips_the_next_instruction_when_the_key_is_pressed() {
+        let mut chip8 = Chip8::new();
+        chip8.reg_mut(RegId(0)).set(Val(5));
+        chip8.key_down(5);
+        let pc_before = chip8.pc();
+        chip8.exec(Instruction::Skp { x: RegId(0) }).unwrap();
+        assert_eq!(chip8.pc().0, pc_before.0 + 4);
+    }
+
+    #[test]
+    fn skp_does_not_skip_when_the_key_is_not_pressed() {
+        let mut chip8 = Chip8::new();
+        chip8.reg_mut(RegId(0)).set(Val(5));
+        let pc_before = chip8.pc();
+        chip8.exec(Instruction::Skp { x: RegId(0) }).unwrap();
+        assert_eq!(chip8.pc().0, pc_before.0 + 2);
+    }
+
+    #[test]
+    fn sknp_skips_the_next_instruction_when_the_key_is_not_pressed() {
+        let mut chip8 = Chip8::new();
+        chip8.reg_mut(RegId(0)).set(Val(5));
+        let pc_before = chip8.pc();
+        chip8.exec(Instruction::Sknp { x: RegId(0) }).unwrap();
+        assert_eq!(chip8.pc().0, pc_before.0 + 4);
+    }
+
+    #[test]
+    fn sknp_does_not_skip_when_the_key_is_pressed() {
+        let mut chip8 = Chip8::new();
+        chip8.reg_mut(RegId(0)).set(Val(5));
+        chip8.key_down(5);
+        let pc_before = chip8.pc();
+        chip8.exec(Instruction::Sknp { x: RegId(0) }).unwrap();
+        assert_eq!(chip8.pc().0, pc_before.0 + 2);
+    }
+
+    #[test]
+    fn ldkey_blocks_until_a_key_latches_then_advances() {
+        let mut chip8 = Chip8::new();
+        let pc_before = chip8.pc();
+
+        // No key down: LdKey must leave PC where it is so the same
+        // instruction re-runs next cycle instead of advancing blind.
+        chip8.exec(Instruction::LdKey { x: RegId(0) }).unwrap();
+        assert_eq!(chip8.pc(), pc_before);
+        assert_eq!(chip8.registers()[0], Val(0));
+
+        chip8.key_down(7);
+        chip8.exec(Instruction::LdKey { x: RegId(0) }).unwrap();
+        assert_eq!(chip8.registers()[0], Val(7));
+        assert_eq!(chip8.pc().0, pc_before.0 + 2);
+    }
+}
@@ -0,0 +1,117 @@
+//! The 60 Hz delay/sound timer subsystem.
+//!
+//! CHIP-8's delay and sound timers run on a fixed 60 Hz clock, independent
+//! of how many instructions the interpreter executes per frame. `Timers`
+//! accumulates elapsed wall-clock time and saturating-decrements both
+//! counters for every ~16.67 ms boundary it crosses, carrying any leftover
+//! fractional time forward instead of dropping it.
+
+use core::time::Duration;
+
+/// Length of one 60 Hz tick, in nanoseconds.
+const FRAME_NS: u64 = 16_666_667;
+
+pub struct Timers {
+    delay: u8,
+    sound: u8,
+    accumulator_ns: u64,
+}
+
+impl Default for Timers {
+    fn default() -> Timers {
+        Timers::new()
+    }
+}
+
+impl Timers {
+    pub fn new() -> Timers {
+        Timers {
+            delay: 0,
+            sound: 0,
+            accumulator_ns: 0,
+        }
+    }
+
+    pub fn delay(&self) -> u8 {
+        self.delay
+    }
+
+    pub fn set_delay(&mut self, delay: u8) {
+        self.delay = delay;
+    }
+
+    pub fn sound(&self) -> u8 {
+        self.sound
+    }
+
+    pub fn set_sound(&mut self, sound: u8) {
+        self.sound = sound;
+    }
+
+    /// Whether the sound timer is active, i.e. a front-end should beep.
+    pub fn sound_active(&self) -> bool {
+        self.sound > 0
+    }
+
+    /// Advance the timers by `elapsed` wall-clock time.
+    pub fn tick(&mut self, elapsed: Duration) {
+        self.accumulator_ns += elapsed.as_nanos() as u64;
+
+        while self.accumulator_ns >= FRAME_NS {
+            self.accumulator_ns -= FRAME_NS;
+            self.delay = self.delay.saturating_sub(1);
+            self.sound = self.sound.saturating_sub(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tick_does_not_decrement_before_a_full_frame_has_elapsed() {
+        let mut timers = Timers::new();
+        timers.set_delay(5);
+        timers.tick(Duration::from_nanos(FRAME_NS - 1));
+        assert_eq!(timers.delay(), 5);
+    }
+
+    #[test]
+    fn tick_decrements_once_per_frame_boundary_crossed() {
+        let mut timers = Timers::new();
+        timers.set_delay(5);
+        timers.set_sound(5);
+        timers.tick(Duration::from_nanos(FRAME_NS * 3));
+        assert_eq!(timers.delay(), 2);
+        assert_eq!(timers.sound(), 2);
+    }
+
+    #[test]
+    fn tick_carries_leftover_fractional_time_across_calls() {
+        let mut timers = Timers::new();
+        timers.set_delay(2);
+
+        // One frame split across two ticks shouldn't lose the remainder.
+        timers.tick(Duration::from_nanos(FRAME_NS - 1));
+        assert_eq!(timers.delay(), 2);
+        timers.tick(Duration::from_nanos(1));
+        assert_eq!(timers.delay(), 1);
+    }
+
+    #[test]
+    fn tick_saturates_instead_of_wrapping_past_zero() {
+        let mut timers = Timers::new();
+        timers.set_delay(1);
+        timers.tick(Duration::from_nanos(FRAME_NS * 5));
+        assert_eq!(timers.delay(), 0);
+    }
+
+    #[test]
+    fn sound_active_tracks_the_sound_timer() {
+        let mut timers = Timers::new();
+        assert!(!timers.sound_active());
+        timers.set_sound(1);
+        assert!(timers.sound_active());
+    }
+}